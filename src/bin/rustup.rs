@@ -0,0 +1,66 @@
+//! Minimal `rustup` entry point exposing `self report-path [--fix]`, which
+//! enumerates the files (and, on Windows, the registry key) rustup's
+//! installer touches and reports (or repairs) their shell integration
+//! without requiring a full reinstall.
+
+use std::env;
+use std::path::PathBuf;
+
+use rustup::self_update::report_path;
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.first().map(String::as_str) != Some("self")
+        || args.get(1).map(String::as_str) != Some("report-path")
+    {
+        eprintln!("usage: rustup self report-path [--fix]");
+        std::process::exit(1);
+    }
+    let fix = args.iter().any(|a| a == "--fix");
+
+    let home = match env::var_os("HOME").or_else(|| env::var_os("USERPROFILE")) {
+        Some(h) => PathBuf::from(h),
+        None => {
+            eprintln!("error: neither HOME nor USERPROFILE is set");
+            std::process::exit(1);
+        }
+    };
+    let cargo_home_display = format!("{}/.cargo", home.display());
+
+    for (rc, dialect) in report_path::rc_candidates(&home) {
+        if !rc.exists() {
+            continue;
+        }
+        if fix {
+            if let Err(e) = report_path::fix_rc(&rc, &cargo_home_display, dialect) {
+                eprintln!("{}: could not fix ({})", rc.display(), e);
+                continue;
+            }
+        }
+        if let Some(status) = report_path::inspect_rc(&rc, &cargo_home_display, dialect) {
+            println!("{}", report_path::format_status(&status, dialect));
+        }
+    }
+
+    report_windows_registry_path(&home, fix);
+}
+
+#[cfg(windows)]
+fn report_windows_registry_path(home: &std::path::Path, fix: bool) {
+    let bin_dir = format!("{}\\.cargo\\bin", home.display());
+    match rustup::shell::windows::registry_has_path(&bin_dir) {
+        Ok(true) => println!("HKCU\\Environment\\PATH: contains {}", bin_dir),
+        Ok(false) => {
+            println!("HKCU\\Environment\\PATH: missing {}", bin_dir);
+            if fix {
+                if let Err(e) = rustup::shell::windows::add_to_registry_path(&bin_dir) {
+                    eprintln!("HKCU\\Environment\\PATH: could not fix ({})", e);
+                }
+            }
+        }
+        Err(e) => eprintln!("HKCU\\Environment\\PATH: could not inspect ({})", e),
+    }
+}
+
+#[cfg(not(windows))]
+fn report_windows_registry_path(_home: &std::path::Path, _fix: bool) {}
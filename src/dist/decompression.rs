@@ -0,0 +1,242 @@
+//! Selects between the `.xz` and `.gz` variants of a dist artifact, since
+//! manifests list both. Recent tarballs use an enlarged xz window, raising
+//! the memory the decoder needs; on memory-constrained machines we'd rather
+//! transparently fetch the `.gz` variant than fail or thrash.
+
+use std::convert::TryFrom;
+use std::env;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Xz,
+    Gz,
+}
+
+impl Format {
+    fn from_override(value: &str) -> Option<Format> {
+        match value.to_ascii_lowercase().as_str() {
+            "xz" => Some(Format::Xz),
+            "gz" | "gzip" => Some(Format::Gz),
+            _ => None,
+        }
+    }
+}
+
+/// Name of the env var that forces the format, bypassing the memory check
+/// entirely. Only the environment is read; there is no config-file key.
+pub const OVERRIDE_ENV_VAR: &str = "RUSTUP_DECOMPRESSION";
+
+const STREAM_HEADER_MAGIC: &[u8] = b"\xFD7zXZ\x00";
+const STREAM_HEADER_LEN: usize = 12; // magic(6) + stream flags(2) + CRC32(4)
+const LZMA2_FILTER_ID: u64 = 0x21;
+
+/// Reads an xz variable-length integer (little-endian base-128: 7 value
+/// bits per byte, high bit set while more bytes follow) starting at
+/// `*pos`, advancing `*pos` past it.
+fn read_vli(bytes: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut result: u64 = 0;
+    for i in 0..9 {
+        let byte = *bytes.get(*pos)?;
+        *pos += 1;
+        result |= u64::from(byte & 0x7f) << (i * 7);
+        if byte & 0x80 == 0 {
+            return Some(result);
+        }
+    }
+    None
+}
+
+/// Estimates the memory (in bytes) the xz decoder will need to allocate for
+/// a stream, from the LZMA2 dictionary-size property encoded in the first
+/// Block Header's filter flags.
+///
+/// `stream_header` must contain the 12-byte Stream Header followed by the
+/// first Block Header (this is normally just the first few dozen bytes of
+/// the artifact, not the whole thing). Filters other than LZMA2 in the
+/// chain are skipped. Returns `None` if the magic doesn't match, the header
+/// is truncated, or no filter in the chain is LZMA2 (in which case we don't
+/// know how to size its window).
+pub fn estimate_xz_memory(stream_header: &[u8]) -> Option<u64> {
+    if stream_header.len() < STREAM_HEADER_LEN
+        || &stream_header[..STREAM_HEADER_MAGIC.len()] != STREAM_HEADER_MAGIC
+    {
+        return None;
+    }
+
+    let mut pos = STREAM_HEADER_LEN;
+    let block_header_size = *stream_header.get(pos)?;
+    if block_header_size == 0 {
+        // Zero here means this is Index padding, not a Block Header.
+        return None;
+    }
+    pos += 1;
+
+    let block_flags = *stream_header.get(pos)?;
+    pos += 1;
+    let num_filters = (block_flags & 0x03) + 1;
+    if block_flags & 0x40 != 0 {
+        read_vli(stream_header, &mut pos)?; // compressed size, unused
+    }
+    if block_flags & 0x80 != 0 {
+        read_vli(stream_header, &mut pos)?; // uncompressed size, unused
+    }
+
+    for _ in 0..num_filters {
+        let filter_id = read_vli(stream_header, &mut pos)?;
+        let props_len = usize::try_from(read_vli(stream_header, &mut pos)?).ok()?;
+        let props = stream_header.get(pos..pos + props_len)?;
+        pos += props_len;
+
+        if filter_id != LZMA2_FILTER_ID {
+            continue;
+        }
+        let dict_byte = *props.first()?;
+        if dict_byte > 40 {
+            return None;
+        }
+        if dict_byte == 40 {
+            return Some(u64::from(u32::MAX));
+        }
+        let n = u32::from(dict_byte);
+        return Some(u64::from(2 | (n & 1)) << (n / 2 + 11));
+    }
+    None
+}
+
+/// Chooses which variant of an artifact to fetch.
+///
+/// `required_xz_memory` is the estimate from [`estimate_xz_memory`] (or
+/// `None` if it couldn't be determined, in which case xz is assumed safe).
+/// `available_memory` is the amount of free system memory in bytes.
+/// `override_choice` takes precedence over both when set.
+pub fn choose_format(
+    required_xz_memory: Option<u64>,
+    available_memory: u64,
+    override_choice: Option<Format>,
+) -> Format {
+    if let Some(format) = override_choice {
+        return format;
+    }
+    match required_xz_memory {
+        Some(needed) if needed > available_memory => Format::Gz,
+        _ => Format::Xz,
+    }
+}
+
+/// Reads [`OVERRIDE_ENV_VAR`] from the environment, if set to a recognized
+/// value.
+pub fn env_override() -> Option<Format> {
+    env::var(OVERRIDE_ENV_VAR)
+        .ok()
+        .and_then(|v| Format::from_override(&v))
+}
+
+/// Available system memory in bytes, or `None` if this platform doesn't
+/// expose a way to tell (in which case [`choose_format`] assumes xz is
+/// safe, same as an unknown `required_xz_memory`).
+#[cfg(target_os = "linux")]
+fn available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    meminfo.lines().find_map(|line| {
+        let kb = line.strip_prefix("MemAvailable:")?.trim().strip_suffix("kB")?;
+        kb.trim().parse::<u64>().ok().map(|kb| kb * 1024)
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+fn available_memory() -> Option<u64> {
+    None
+}
+
+/// Indicates the active xz decoder ran out of memory allocating its
+/// dictionary/window, e.g. because [`estimate_xz_memory`] under-estimated
+/// (or couldn't read) the requirement.
+#[derive(Debug)]
+pub struct OutOfMemory;
+
+/// Fetches a component: estimates the `.xz` variant's memory requirement
+/// from `xz_stream_header`, picks a format via [`choose_format`] and
+/// [`env_override`], and calls `fetch_xz` or `fetch_gz` accordingly.
+///
+/// If `fetch_xz` itself reports [`OutOfMemory`] (the estimate missed this
+/// machine's actual limits), this transparently retries with `fetch_gz`,
+/// same as the format selection that ran before the fetch.
+pub fn fetch_component<T>(
+    xz_stream_header: &[u8],
+    fetch_xz: impl FnOnce() -> Result<T, OutOfMemory>,
+    fetch_gz: impl FnOnce() -> T,
+) -> T {
+    let required = estimate_xz_memory(xz_stream_header);
+    let available = available_memory().unwrap_or(u64::MAX);
+    match choose_format(required, available, env_override()) {
+        Format::Gz => fetch_gz(),
+        Format::Xz => fetch_xz().unwrap_or_else(|OutOfMemory| fetch_gz()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unrecognized_header_is_not_estimated() {
+        assert_eq!(estimate_xz_memory(b"not an xz stream"), None);
+    }
+
+    fn xz_header_with_dict_byte(n: u8) -> Vec<u8> {
+        let mut header = STREAM_HEADER_MAGIC.to_vec();
+        header.extend_from_slice(&[0, 0, 0, 0, 0, 0]); // stream flags + CRC32
+        header.push(0x08); // block header size
+        header.push(0x00); // block flags: 1 filter, no size fields
+        header.push(0x21); // filter ID: LZMA2
+        header.push(0x01); // size of properties: 1 byte
+        header.push(n);
+        header
+    }
+
+    #[test]
+    fn maximum_dictionary_byte_estimates_u32_max() {
+        let header = xz_header_with_dict_byte(40);
+        assert_eq!(estimate_xz_memory(&header), Some(u64::from(u32::MAX)));
+    }
+
+    #[test]
+    fn low_memory_falls_back_to_gzip() {
+        assert_eq!(
+            choose_format(Some(64 * 1024 * 1024), 8 * 1024 * 1024, None),
+            Format::Gz
+        );
+    }
+
+    #[test]
+    fn sufficient_memory_keeps_xz() {
+        assert_eq!(
+            choose_format(Some(8 * 1024 * 1024), 64 * 1024 * 1024, None),
+            Format::Xz
+        );
+    }
+
+    #[test]
+    fn override_wins_regardless_of_memory() {
+        assert_eq!(
+            choose_format(Some(1), u64::MAX, Some(Format::Gz)),
+            Format::Gz
+        );
+    }
+
+    #[test]
+    fn fetch_component_retries_gzip_on_allocation_failure() {
+        let picked = fetch_component(b"not an xz stream", || Err(OutOfMemory), || "gz");
+        assert_eq!(picked, "gz");
+    }
+
+    #[test]
+    fn fetch_component_leaves_gzip_untouched_when_xz_succeeds() {
+        let picked = fetch_component(
+            b"not an xz stream",
+            || Ok("xz"),
+            || unreachable!("gzip should not be fetched when xz succeeds"),
+        );
+        assert_eq!(picked, "xz");
+    }
+}
@@ -0,0 +1,7 @@
+//! Support library backing rustup's shell-integration and dist-decompression
+//! logic, factored out so it can be exercised directly by integration tests.
+
+pub mod dist;
+pub mod self_update;
+pub mod shell;
+pub mod utils;
@@ -0,0 +1,145 @@
+//! Backing logic for `rustup self report-path`: enumerate the files rustup
+//! would edit to put `CARGO_HOME/bin` on `PATH`, and report whether each one
+//! is already integrated, untouched, or still carries a stale legacy
+//! export line.
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::shell::{self, EnvDialect};
+
+/// Enumerates every rc file rustup's installer could have touched for
+/// `home`, paired with the dialect each one is written in.
+///
+/// Honors `$ZDOTDIR` for the zsh rc, same as the installer itself: when
+/// set, zsh reads `.zshenv` from there instead of `home`.
+pub fn rc_candidates(home: &Path) -> Vec<(PathBuf, EnvDialect)> {
+    let zsh_home = env::var_os("ZDOTDIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.to_path_buf());
+
+    let mut targets: Vec<(PathBuf, EnvDialect)> = [".bashrc", ".bash_profile", ".bash_login", ".profile"]
+        .iter()
+        .map(|rc| (home.join(rc), EnvDialect::Posix))
+        .collect();
+    targets.push((zsh_home.join(".zshenv"), EnvDialect::Posix));
+    targets.push((shell::fish_conf_d_file(home), EnvDialect::Fish));
+    targets
+}
+
+/// The old, unguarded line installs before `env.sh`/`env.fish` existed.
+/// Newer installs clean this up on sight; `report-path` flags it if it's
+/// still lurking in a file that was never reinstalled.
+const LEGACY_EXPORT: &str = "export PATH=\"$HOME/.cargo/bin:$PATH\"";
+
+/// One rc (or env) file rustup might touch, and its current state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RcStatus {
+    pub path: PathBuf,
+    /// `true` if the file already sources the canonical env file.
+    pub integrated: bool,
+    /// `true` if the file still has the legacy unguarded export line.
+    pub legacy_export: bool,
+}
+
+/// Inspects a single rc file against the canonical integration for
+/// `cargo_home_display`/`dialect`. Returns `None` if the file doesn't exist.
+pub fn inspect_rc(path: &Path, cargo_home_display: &str, dialect: EnvDialect) -> Option<RcStatus> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let source_line = shell::source_line(cargo_home_display, dialect.file_name());
+    Some(RcStatus {
+        path: path.to_path_buf(),
+        integrated: contents.contains(source_line.trim_end()),
+        legacy_export: contents.contains(LEGACY_EXPORT),
+    })
+}
+
+/// Renders a human-readable report line for a single file.
+pub fn format_status(status: &RcStatus, dialect: EnvDialect) -> String {
+    if status.legacy_export {
+        format!(
+            "{}: legacy PATH export found, {} {}",
+            status.path.display(),
+            if status.integrated { "also sourcing" } else { "not sourcing" },
+            dialect.file_name()
+        )
+    } else if status.integrated {
+        format!("{}: sourcing {}", status.path.display(), dialect.file_name())
+    } else {
+        format!("{}: not integrated", status.path.display())
+    }
+}
+
+/// Re-applies the canonical integration to `path`: removes any legacy
+/// export line and ensures the rc sources the given env file, exactly as
+/// `rustup-init` would on a fresh install.
+pub fn fix_rc(path: &Path, cargo_home_display: &str, dialect: EnvDialect) -> io::Result<()> {
+    let contents = std::fs::read_to_string(path)?;
+    let without_legacy = contents
+        .replace(&format!("\n{}\n", LEGACY_EXPORT), "\n")
+        .replace(LEGACY_EXPORT, "");
+    let fixed = shell::add_source(&without_legacy, cargo_home_display, dialect.file_name());
+    std::fs::write(path, fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn flags_legacy_export() {
+        let dir = tempfile::tempdir().unwrap();
+        let rc = dir.path().join(".profile");
+        fs::write(&rc, format!("foo\n{}\n", LEGACY_EXPORT)).unwrap();
+
+        let status = inspect_rc(&rc, "$HOME/.cargo", EnvDialect::Posix).unwrap();
+        assert!(status.legacy_export);
+        assert!(!status.integrated);
+    }
+
+    #[test]
+    fn rc_candidates_honors_zdotdir_for_the_zsh_rc() {
+        let home = dir_for_rc_candidates_test();
+        let zdotdir = tempfile::tempdir().unwrap();
+        env::set_var("ZDOTDIR", zdotdir.path());
+
+        let targets = rc_candidates(&home);
+        env::remove_var("ZDOTDIR");
+
+        let zshenv = targets
+            .iter()
+            .find(|(path, _)| path.file_name().unwrap() == ".zshenv")
+            .unwrap();
+        assert_eq!(zshenv.0, zdotdir.path().join(".zshenv"));
+        assert_eq!(zshenv.1, EnvDialect::Posix);
+    }
+
+    #[test]
+    fn rc_candidates_includes_the_fish_conf_d_snippet() {
+        let home = dir_for_rc_candidates_test();
+        let targets = rc_candidates(&home);
+        assert!(targets
+            .iter()
+            .any(|(path, dialect)| *dialect == EnvDialect::Fish
+                && *path == shell::fish_conf_d_file(&home)));
+    }
+
+    fn dir_for_rc_candidates_test() -> PathBuf {
+        tempfile::tempdir().unwrap().keep()
+    }
+
+    #[test]
+    fn fix_removes_legacy_and_adds_canonical_source() {
+        let dir = tempfile::tempdir().unwrap();
+        let rc = dir.path().join(".profile");
+        fs::write(&rc, format!("foo\n{}\n", LEGACY_EXPORT)).unwrap();
+
+        fix_rc(&rc, "$HOME/.cargo", EnvDialect::Posix).unwrap();
+
+        let fixed = fs::read_to_string(&rc).unwrap();
+        assert!(!fixed.contains(LEGACY_EXPORT));
+        assert!(fixed.contains(r#"source "$HOME/.cargo/env.sh""#));
+    }
+}
@@ -0,0 +1,162 @@
+//! Generation of the `env.*` scripts rustup installs into `CARGO_HOME`, and
+//! the rc-file edits that source them.
+//!
+//! Every shell rustup supports gets the same two things: a small env file
+//! inside `CARGO_HOME` that puts `bin` on `PATH` (idempotently, so sourcing
+//! it twice is harmless), and a single line added to that shell's rc file(s)
+//! that sources it.
+
+#[cfg(windows)]
+pub mod windows;
+
+/// One of the env-file "dialects" rustup can generate. Each dialect has its
+/// own syntax for extending `PATH`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvDialect {
+    /// POSIX `sh`-compatible syntax, sourced by bash, zsh, and similar
+    /// shells via `.profile`/`.bashrc`/`.zshenv`.
+    Posix,
+    /// Fish's own syntax, sourced from a snippet under
+    /// `~/.config/fish/conf.d`.
+    Fish,
+}
+
+impl EnvDialect {
+    /// File name of the generated env file within `CARGO_HOME`.
+    pub fn file_name(self) -> &'static str {
+        match self {
+            EnvDialect::Posix => "env.sh",
+            EnvDialect::Fish => "env.fish",
+        }
+    }
+
+    /// Contents of the env file itself, given the display form of
+    /// `CARGO_HOME` to embed (e.g. `"$HOME/.cargo"` or an absolute path).
+    ///
+    /// For [`EnvDialect::Posix`], `cargo_home_display` is run through
+    /// [`sanitize_sh`] first, since this file is itself sourced by a POSIX
+    /// shell and can't dereference a native Windows path.
+    pub fn env_file_contents(self, cargo_home_display: &str) -> String {
+        match self {
+            EnvDialect::Posix => posix_env_file(&sanitize_sh(cargo_home_display)),
+            EnvDialect::Fish => fish_env_file(cargo_home_display),
+        }
+    }
+}
+
+fn posix_env_file(cargo_home_display: &str) -> String {
+    format!(
+        "#!/bin/sh\n\
+# rustup shell setup\n\
+# affix colons on either side of $PATH to simplify matching\n\
+case \":${{PATH}}:\" in\n    \
+    *:\"{home}/bin\":*)\n        \
+        ;;\n    \
+    *)\n        \
+        export PATH=\"{home}/bin:$PATH\"\n        \
+        ;;\n\
+esac\n",
+        home = cargo_home_display
+    )
+}
+
+fn fish_env_file(cargo_home_display: &str) -> String {
+    format!(
+        "# rustup shell setup\n\
+if not contains \"{home}/bin\" $PATH\n    \
+    set -gx PATH \"{home}/bin\" $PATH\n\
+end\n",
+        home = cargo_home_display
+    )
+}
+
+/// Path to the guarded Fish snippet rustup installs under
+/// `~/.config/fish/conf.d`, given the user's home directory.
+pub fn fish_conf_d_file(home_dir: &std::path::Path) -> std::path::PathBuf {
+    home_dir.join(".config/fish/conf.d/rustup.fish")
+}
+
+/// The `source` line that loads `file_name` out of `cargo_home_display`.
+///
+/// `cargo_home_display` is run through [`sanitize_sh`] first: this line
+/// ends up in a POSIX rc file (or conf.d snippet), which can't `source` a
+/// native Windows path even when the dialect that generated it could.
+pub fn source_line(cargo_home_display: &str, file_name: &str) -> String {
+    format!("source \"{}/{}\"\n", sanitize_sh(cargo_home_display), file_name)
+}
+
+/// Appends a `source` line for the given env file to `rc_contents`, unless
+/// it's already present.
+pub fn add_source(rc_contents: &str, cargo_home_display: &str, file_name: &str) -> String {
+    let line = source_line(cargo_home_display, file_name);
+    if rc_contents.contains(line.trim_end()) {
+        return rc_contents.to_string();
+    }
+    if rc_contents.is_empty() {
+        line
+    } else {
+        format!("{}\n{}", rc_contents.trim_end_matches('\n'), line)
+    }
+}
+
+/// Removes a previously added `source` line for the given env file from
+/// `rc_contents`, if present.
+pub fn remove_source(rc_contents: &str, cargo_home_display: &str, file_name: &str) -> String {
+    let line = source_line(cargo_home_display, file_name);
+    let trimmed_line = line.trim_end();
+    let mut out = String::new();
+    for l in rc_contents.lines() {
+        if l == trimmed_line {
+            continue;
+        }
+        out.push_str(l);
+        out.push('\n');
+    }
+    out
+}
+
+/// Normalizes a native Windows-style `CARGO_HOME` (as seen under Git
+/// Bash/MSYS2/Cygwin) into a path a POSIX shell can actually `source`.
+///
+/// Strips a leading `//?/` UNC prefix, flips `\` separators to `/`, and
+/// rewrites a leading drive letter (`C:/`) into the `/c/` form these shells
+/// mount drives under.
+pub fn sanitize_sh(path: &str) -> String {
+    let path = path.strip_prefix("//?/").unwrap_or(path);
+    let path = path.replace('\\', "/");
+
+    let bytes = path.as_bytes();
+    if bytes.len() >= 2 && bytes[1] == b':' && bytes[0].is_ascii_alphabetic() {
+        format!("/{}{}", (bytes[0] as char).to_ascii_lowercase(), &path[2..])
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn posix_env_file_has_the_expected_default_export() {
+        let contents = posix_env_file("$HOME/.cargo");
+        assert!(contents.contains(r#"export PATH="$HOME/.cargo/bin:$PATH""#));
+    }
+
+    #[test]
+    fn add_source_is_idempotent() {
+        let once = add_source("foo\nbar\nbaz", "$HOME/.cargo", "env.sh");
+        let twice = add_source(&once, "$HOME/.cargo", "env.sh");
+        assert_eq!(once, twice);
+    }
+
+    #[test]
+    fn sanitize_sh_rewrites_windows_drive_paths() {
+        assert_eq!(sanitize_sh(r"C:\Users\me\.cargo"), "/c/Users/me/.cargo");
+        assert_eq!(
+            sanitize_sh(r"//?/C:\Users\me\.cargo"),
+            "/c/Users/me/.cargo"
+        );
+        assert_eq!(sanitize_sh("/home/me/.cargo"), "/home/me/.cargo");
+    }
+}
@@ -0,0 +1,205 @@
+//! Windows-specific shell integration: a guarded block in the current
+//! user's PowerShell `$PROFILE`, plus the `HKCU\Environment\PATH` registry
+//! edit most installs still rely on. Both are maintained side by side
+//! because PowerShell sessions don't always re-read that registry value
+//! after it changes.
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use winreg::enums::{RegType, HKEY_CURRENT_USER, KEY_READ, KEY_WRITE};
+use winreg::{RegKey, RegValue};
+
+const GUARD_START: &str = "# ::rustup-path-start::";
+const GUARD_END: &str = "# ::rustup-path-end::";
+const ENVIRONMENT_KEY: &str = "Environment";
+const PATH_VALUE: &str = "PATH";
+
+/// The guarded block inserted into `$PROFILE`. `cargo_home_bin_display` is
+/// the display form of `CARGO_HOME\bin` to prepend onto `$env:Path`.
+fn profile_block(cargo_home_bin_display: &str) -> String {
+    format!(
+        "{}\n$env:Path = \"{};$env:Path\"\n{}\n",
+        GUARD_START, cargo_home_bin_display, GUARD_END
+    )
+}
+
+/// Adds the guarded block to `profile_contents`, replacing any existing one
+/// so repeated installs don't grow the file.
+pub fn add_to_profile(profile_contents: &str, cargo_home_bin_display: &str) -> String {
+    let without_existing = remove_from_profile(profile_contents);
+    format!("{}{}", without_existing, profile_block(cargo_home_bin_display))
+}
+
+/// Removes the guarded block from `profile_contents`, if present.
+pub fn remove_from_profile(profile_contents: &str) -> String {
+    match (
+        profile_contents.find(GUARD_START),
+        profile_contents.find(GUARD_END),
+    ) {
+        (Some(start), Some(end)) => {
+            let end = end + GUARD_END.len();
+            let mut result = String::new();
+            result.push_str(&profile_contents[..start]);
+            let after = &profile_contents[end..];
+            result.push_str(after.strip_prefix('\n').unwrap_or(after));
+            result
+        }
+        _ => profile_contents.to_string(),
+    }
+}
+
+/// Locates the current user's PowerShell `$PROFILE` (the "current user,
+/// current host" profile `powershell.exe` reads on startup). Returns `None`
+/// if `USERPROFILE` isn't set, which shouldn't happen on a real Windows
+/// session.
+pub fn profile_path() -> Option<PathBuf> {
+    let documents = PathBuf::from(std::env::var_os("USERPROFILE")?).join("Documents");
+    Some(
+        documents
+            .join("WindowsPowerShell")
+            .join("Microsoft.PowerShell_profile.ps1"),
+    )
+}
+
+/// Appends the guarded PATH block to `$PROFILE`, creating the file (and its
+/// parent directories) on first install.
+pub fn install_profile(cargo_home_bin_display: &str) -> io::Result<()> {
+    let path = profile_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "USERPROFILE is not set"))?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    fs::write(&path, add_to_profile(&existing, cargo_home_bin_display))
+}
+
+/// Removes the guarded PATH block from `$PROFILE`, if the file exists.
+pub fn uninstall_profile() -> io::Result<()> {
+    let path = match profile_path() {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let existing = match fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(_) => return Ok(()),
+    };
+    fs::write(&path, remove_from_profile(&existing))
+}
+
+fn environment_key(write: bool) -> io::Result<RegKey> {
+    let flags = if write { KEY_READ | KEY_WRITE } else { KEY_READ };
+    RegKey::predef(HKEY_CURRENT_USER).open_subkey_with_flags(ENVIRONMENT_KEY, flags)
+}
+
+fn registry_string_to_utf16(s: &str) -> Vec<u8> {
+    let utf16: Vec<u16> = s.encode_utf16().chain(Some(0)).collect();
+    utf16.iter().flat_map(|c| c.to_le_bytes()).collect()
+}
+
+fn utf16_to_registry_string(bytes: &[u8]) -> String {
+    let words: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&words)
+        .trim_end_matches('\u{0}')
+        .to_string()
+}
+
+fn read_registry_path(env: &RegKey) -> Option<String> {
+    env.get_raw_value(PATH_VALUE)
+        .ok()
+        .map(|v| utf16_to_registry_string(&v.bytes))
+}
+
+/// Reports whether `bin_dir` is already present in `HKCU\Environment\PATH`.
+pub fn registry_has_path(bin_dir: &str) -> io::Result<bool> {
+    let env = environment_key(false)?;
+    Ok(read_registry_path(&env)
+        .map(|path| path.split(';').any(|p| p == bin_dir))
+        .unwrap_or(false))
+}
+
+/// Prepends `bin_dir` onto `HKCU\Environment\PATH`, unless it's already
+/// there. Creates the value (as `REG_EXPAND_SZ`, matching how Windows
+/// itself stores `PATH`) if it doesn't exist yet.
+pub fn add_to_registry_path(bin_dir: &str) -> io::Result<()> {
+    let env = environment_key(true)?;
+    let old_path = read_registry_path(&env).unwrap_or_default();
+    if old_path.split(';').any(|p| p == bin_dir) {
+        return Ok(());
+    }
+    let new_path = if old_path.is_empty() {
+        bin_dir.to_string()
+    } else {
+        format!("{};{}", bin_dir, old_path)
+    };
+    env.set_raw_value(
+        PATH_VALUE,
+        &RegValue {
+            bytes: registry_string_to_utf16(&new_path),
+            vtype: RegType::REG_EXPAND_SZ,
+        },
+    )
+}
+
+/// Removes `bin_dir` from `HKCU\Environment\PATH`, deleting the value
+/// entirely if it was the only entry left.
+pub fn remove_from_registry_path(bin_dir: &str) -> io::Result<()> {
+    let env = environment_key(true)?;
+    let old_path = match read_registry_path(&env) {
+        Some(path) => path,
+        None => return Ok(()),
+    };
+    let remaining: Vec<&str> = old_path
+        .split(';')
+        .filter(|p| *p != bin_dir && !p.is_empty())
+        .collect();
+    if remaining.is_empty() {
+        let _ = env.delete_value(PATH_VALUE);
+        Ok(())
+    } else {
+        env.set_raw_value(
+            PATH_VALUE,
+            &RegValue {
+                bytes: registry_string_to_utf16(&remaining.join(";")),
+                vtype: RegType::REG_EXPAND_SZ,
+            },
+        )
+    }
+}
+
+/// Installs both halves of Windows shell integration: the registry `PATH`
+/// edit and the `$PROFILE` block.
+pub fn install(cargo_home_bin_display: &str) -> io::Result<()> {
+    add_to_registry_path(cargo_home_bin_display)?;
+    install_profile(cargo_home_bin_display)
+}
+
+/// Undoes [`install`].
+pub fn uninstall(cargo_home_bin_display: &str) -> io::Result<()> {
+    remove_from_registry_path(cargo_home_bin_display)?;
+    uninstall_profile()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_then_remove_round_trips() {
+        let profile = "Write-Host 'hi'\n";
+        let with_block = add_to_profile(profile, r"$env:USERPROFILE\.cargo\bin");
+        assert_ne!(with_block, profile);
+        assert_eq!(remove_from_profile(&with_block), profile);
+    }
+
+    #[test]
+    fn adding_twice_does_not_duplicate_the_block() {
+        let once = add_to_profile("", r"$env:USERPROFILE\.cargo\bin");
+        let twice = add_to_profile(&once, r"$env:USERPROFILE\.cargo\bin");
+        assert_eq!(once, twice);
+    }
+}
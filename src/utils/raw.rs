@@ -0,0 +1,18 @@
+//! Small filesystem helpers shared by the shell-integration code.
+
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+/// Writes `contents` to `path`, creating the file if necessary and replacing
+/// any existing contents.
+pub fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+/// Appends `contents` to the end of `path`, creating it if necessary.
+pub fn append_file(path: &Path, contents: &str) -> io::Result<()> {
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(contents.as_bytes())
+}
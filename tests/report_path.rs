@@ -0,0 +1,95 @@
+//! Exercises `rustup self report-path`'s backing logic: flagging stale
+//! legacy exports, reporting already-integrated rcs, and `--fix` re-
+//! applying the canonical integration, the same scenarios the legacy-
+//! cleanup tests in `cli-paths.rs` cover end-to-end.
+use rustup::self_update::report_path::{fix_rc, format_status, inspect_rc, rc_candidates};
+use rustup::shell::EnvDialect;
+use std::fs;
+
+const FAKE_RC: &str = "\n# Sources fruity punch.\nsource ~/fruit/punch\n\n# Adds apples to PATH.\nexport PATH=\"$HOME/apple/bin\"\n";
+const LEGACY_EXPORT: &str = "export PATH=\"$HOME/.cargo/bin:$PATH\"";
+
+#[test]
+fn flags_legacy_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let rc = dir.path().join(".profile");
+    fs::write(&rc, format!("{}{}\n", FAKE_RC, LEGACY_EXPORT)).unwrap();
+
+    let status = inspect_rc(&rc, "$HOME/.cargo", EnvDialect::Posix).unwrap();
+    assert!(status.legacy_export);
+    assert!(!status.integrated);
+    assert_eq!(
+        format_status(&status, EnvDialect::Posix),
+        format!(
+            "{}: legacy PATH export found, not sourcing env.sh",
+            rc.display()
+        )
+    );
+}
+
+#[test]
+fn reports_already_integrated_rc() {
+    let dir = tempfile::tempdir().unwrap();
+    let rc = dir.path().join(".profile");
+    fs::write(&rc, "foo\nsource \"$HOME/.cargo/env.sh\"\n").unwrap();
+
+    let status = inspect_rc(&rc, "$HOME/.cargo", EnvDialect::Posix).unwrap();
+    assert!(status.integrated);
+    assert!(!status.legacy_export);
+    assert_eq!(
+        format_status(&status, EnvDialect::Posix),
+        format!("{}: sourcing env.sh", rc.display())
+    );
+}
+
+#[test]
+fn fix_reapplies_canonical_integration() {
+    let dir = tempfile::tempdir().unwrap();
+    let rc = dir.path().join(".profile");
+    fs::write(&rc, format!("{}{}\n", FAKE_RC, LEGACY_EXPORT)).unwrap();
+
+    fix_rc(&rc, "$HOME/.cargo", EnvDialect::Posix).unwrap();
+
+    let fixed = fs::read_to_string(&rc).unwrap();
+    assert!(!fixed.contains(LEGACY_EXPORT));
+    assert!(fixed.contains(r#"source "$HOME/.cargo/env.sh""#));
+
+    let status = inspect_rc(&rc, "$HOME/.cargo", EnvDialect::Posix).unwrap();
+    assert_eq!(
+        format_status(&status, EnvDialect::Posix),
+        format!("{}: sourcing env.sh", rc.display())
+    );
+}
+
+#[test]
+fn rc_candidates_covers_zdotdir_and_fish_not_just_the_hardcoded_posix_rcs() {
+    let home = tempfile::tempdir().unwrap();
+    let zdotdir = tempfile::tempdir().unwrap();
+    std::env::set_var("ZDOTDIR", zdotdir.path());
+
+    let targets = rc_candidates(home.path());
+    std::env::remove_var("ZDOTDIR");
+
+    assert!(targets.contains(&(home.path().join(".bashrc"), EnvDialect::Posix)));
+    assert!(targets.contains(&(zdotdir.path().join(".zshenv"), EnvDialect::Posix)));
+    assert!(targets.contains(&(
+        home.path().join(".config/fish/conf.d/rustup.fish"),
+        EnvDialect::Fish
+    )));
+}
+
+#[test]
+fn fix_applies_the_fish_dialect_to_the_fish_conf_d_snippet() {
+    let home = tempfile::tempdir().unwrap();
+    let (fish_rc, dialect) = rc_candidates(home.path())
+        .into_iter()
+        .find(|(_, d)| *d == EnvDialect::Fish)
+        .unwrap();
+    fs::create_dir_all(fish_rc.parent().unwrap()).unwrap();
+    fs::write(&fish_rc, "").unwrap();
+
+    fix_rc(&fish_rc, "$HOME/.cargo", dialect).unwrap();
+
+    let fixed = fs::read_to_string(&fish_rc).unwrap();
+    assert!(fixed.contains(r#"source "$HOME/.cargo/env.fish""#));
+}
@@ -0,0 +1,38 @@
+//! Exercises the Fish shell backend: env.fish generation and the guarded
+//! conf.d snippet that sources it, mirroring the zsh/bash coverage in
+//! `cli-paths.rs`.
+use rustup::shell::{self, EnvDialect};
+
+#[test]
+fn fish_env_file_sets_path_with_guard() {
+    let contents = EnvDialect::Fish.env_file_contents("$HOME/.cargo");
+    assert!(contents.contains(r#"set -gx PATH "$HOME/.cargo/bin" $PATH"#));
+    // Re-sourcing env.fish shouldn't grow PATH a second time.
+    assert!(contents.contains("if not contains"));
+}
+
+#[test]
+fn fish_conf_d_file_lives_under_the_users_config_dir() {
+    let home = tempfile::tempdir().unwrap();
+    let conf_d_file = shell::fish_conf_d_file(home.path());
+    assert_eq!(
+        conf_d_file,
+        home.path().join(".config/fish/conf.d/rustup.fish")
+    );
+}
+
+#[test]
+fn conf_d_snippet_sources_env_fish() {
+    let line = shell::source_line("/opt/cargo", EnvDialect::Fish.file_name());
+    assert_eq!(line, "source \"/opt/cargo/env.fish\"\n");
+}
+
+#[test]
+fn conf_d_snippet_is_added_once_and_removed_cleanly() {
+    let once = shell::add_source("", "/opt/cargo", EnvDialect::Fish.file_name());
+    let twice = shell::add_source(&once, "/opt/cargo", EnvDialect::Fish.file_name());
+    assert_eq!(once, twice);
+
+    let removed = shell::remove_source(&once, "/opt/cargo", EnvDialect::Fish.file_name());
+    assert_eq!(removed, "");
+}
@@ -0,0 +1,43 @@
+//! Exercises sanitizing Windows-style `CARGO_HOME` paths before they're
+//! written into a POSIX rc file's `source` line (Git Bash/MSYS2/Cygwin).
+use rustup::shell::{self, sanitize_sh, EnvDialect};
+
+#[test]
+fn rewrites_drive_letter_and_backslashes() {
+    assert_eq!(sanitize_sh(r"C:\Users\me\.cargo"), "/c/Users/me/.cargo");
+}
+
+#[test]
+fn strips_leading_unc_prefix() {
+    assert_eq!(
+        sanitize_sh(r"//?/C:\Users\me\.cargo"),
+        "/c/Users/me/.cargo"
+    );
+}
+
+#[test]
+fn lowercases_the_drive_letter() {
+    assert_eq!(sanitize_sh(r"D:\tools\cargo"), "/d/tools/cargo");
+}
+
+#[test]
+fn leaves_already_posix_paths_alone() {
+    assert_eq!(sanitize_sh("/home/me/.cargo"), "/home/me/.cargo");
+}
+
+// The unit above proves `sanitize_sh` itself is correct; these drive the
+// actual rc-writing helpers it's supposed to be feeding, the way
+// `install_updates_bash_rcs` would under Git Bash with a native CARGO_HOME.
+
+#[test]
+fn install_updates_bash_rcs_with_a_windows_style_cargo_home() {
+    let rc = shell::add_source("foo\nbar\nbaz", r"C:\Users\me\.cargo", "env.sh");
+    assert!(rc.contains(r#"source "/c/Users/me/.cargo/env.sh""#));
+    assert!(!rc.contains(r"C:\Users\me\.cargo"));
+}
+
+#[test]
+fn env_file_contents_sanitizes_a_windows_style_cargo_home() {
+    let contents = EnvDialect::Posix.env_file_contents(r"C:\Users\me\.cargo");
+    assert!(contents.contains(r#"export PATH="/c/Users/me/.cargo/bin:$PATH""#));
+}
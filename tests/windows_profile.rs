@@ -0,0 +1,54 @@
+#![cfg(windows)]
+//! Exercises the PowerShell `$PROFILE` backend maintained alongside the
+//! registry PATH edit on Windows installs.
+use rustup::shell::windows::{
+    add_to_profile, install_profile, profile_path, remove_from_profile, uninstall_profile,
+};
+
+const HOME_BIN: &str = r"$env:USERPROFILE\.cargo\bin";
+
+#[test]
+fn adds_guarded_block() {
+    let updated = add_to_profile("", HOME_BIN);
+    assert!(updated.contains(r#"$env:Path = "$env:USERPROFILE\.cargo\bin;$env:Path""#));
+}
+
+#[test]
+fn adding_twice_does_not_duplicate_the_block() {
+    let once = add_to_profile("", HOME_BIN);
+    let twice = add_to_profile(&once, HOME_BIN);
+    assert_eq!(once, twice);
+}
+
+#[test]
+fn removes_exactly_the_guarded_block() {
+    let my_profile = "Write-Host 'hi'\n";
+    let with_block = add_to_profile(my_profile, HOME_BIN);
+    assert_eq!(remove_from_profile(&with_block), my_profile);
+}
+
+// The tests above prove `add_to_profile`/`remove_from_profile` are correct
+// in isolation; this drives the real `$PROFILE` file install/uninstall
+// path that's supposed to call them.
+#[test]
+fn install_and_uninstall_profile_touch_the_real_profile_file() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var("USERPROFILE", home.path());
+
+    install_profile(HOME_BIN).unwrap();
+    let path = profile_path().unwrap();
+    assert!(path.exists());
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(contents.contains(HOME_BIN));
+
+    // Installing twice shouldn't duplicate the guarded block.
+    install_profile(HOME_BIN).unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.matches(HOME_BIN).count(), 1);
+
+    uninstall_profile().unwrap();
+    let contents = std::fs::read_to_string(&path).unwrap();
+    assert!(!contents.contains(HOME_BIN));
+
+    std::env::remove_var("USERPROFILE");
+}